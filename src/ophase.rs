@@ -1,12 +1,13 @@
-use std::sync::{
-	Arc,
-	Mutex,
-};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::Utc;
 use log::{
 	debug,
 	info,
 	trace,
+	warn,
 };
 use poise::{
 	Command,
@@ -17,115 +18,313 @@ use serenity::all::{
 	CacheHttp,
 	ChannelId,
 	Color,
+	ComponentInteraction,
+	CreateActionRow,
+	CreateButton,
 	CreateEmbed,
+	CreateInteractionResponse,
+	CreateInteractionResponseMessage,
+	CreateMessage,
+	GuildId,
+	Member,
 	PartialGuild,
 	Ready,
 	RoleId,
 };
+use tokio::sync::Mutex;
 
 use crate::{
 	config::{
 		Config,
 		OPhase,
+		OPhaseGroup,
 	},
 	AppState,
 	Error,
 };
 
+/// Je Server die zuletzt beobachtete `uses`-Anzahl jedes Invite-Codes.
+pub type InviteUseCache = Arc<Mutex<HashMap<GuildId, HashMap<String, u64>>>>;
+
+/// `custom_id` des dauerhaften Verifizierungs-Buttons, siehe [`post_verify_button`].
+const VERIFY_BUTTON_ID: &str = "ophase_verify";
+
 pub fn register_commands(commands: &mut Vec<Command<AppState, Error>>) {
 	commands.push(ersti());
+	commands.push(post_verify_button());
+	commands.push(ophase_config());
 }
 
-async fn get_role_id(guild: impl Into<PartialGuild>, config: &OPhase) -> Result<RoleId, Error> {
-	let guild: PartialGuild = guild.into();
-	let Some(role) = guild.role_by_name(&config.role_name) else {
-		return Err("Keine Rolle mit dem Namen der O-Phasen-Rolle gefunden".into());
-	};
-	Ok(role.id)
+fn get_role_id_by_name(guild: &PartialGuild, role_name: &str) -> Option<RoleId> {
+	guild.role_by_name(role_name).map(|role| role.id)
 }
 
-async fn get_channel_id(ctx: poise::ApplicationContext<'_, AppState, Error>, config: &OPhase) -> Result<ChannelId, Error> {
-	let guild = ctx
-		.guild()
-		.ok_or("Dieser Befehl kann nur in einem Server ausgeführt werden.")?
-		.clone();
-	guild
-		.channels(ctx)
+async fn get_channel_id(http: impl CacheHttp, guild_id: GuildId, channel_name: &str) -> Result<ChannelId, Error> {
+	guild_id
+		.channels(http)
 		.await?
 		.into_iter()
-		.find(|(_, channel)| channel.name() == config.channel_name)
+		.find(|(_, channel)| channel.name == channel_name)
 		.map(|(id, _)| id)
 		.ok_or("Kanal für die O-Phase nicht gefunden".into())
 }
 
-pub async fn get_ophase_invite_count(ctx: &poise::serenity_prelude::Context, ready: &Ready, config: &Config) -> Option<u64> {
-	if let Some(o_phase_config) = &config.o_phase {
-		let mut invite = None;
-		trace!("Ready: {:#?}", ready);
-		for guild in ready.guilds.iter() {
-			let invites = guild
-				.id
-				.invites(ctx.http())
-				.await
-				.unwrap_or_else(|e| panic!("Could not get invites for guild {:?}: {e:?}", guild.id));
-			trace!("Found invites in guild {:?}: {:?}", guild, invites);
-			invite = invites.into_iter().find(|invite| invite.code == o_phase_config.invite_code);
-			if invite.is_some() {
+/// Findet die Tutorgruppe, deren Passwort (ohne Beachtung der Groß-/Kleinschreibung)
+/// zum eingegebenen Passwort passt.
+fn find_matching_group<'a>(config: &'a OPhase, password: &str) -> Option<&'a OPhaseGroup> {
+	config.groups.iter().find(|group| group.password.to_lowercase() == password.to_lowercase())
+}
+
+/// Plant die automatische Entfernung aller O-Phasen-Rollen zum konfigurierten
+/// `end_time`. Die Aufgabe schläft im Hintergrund bis zu diesem Zeitpunkt und
+/// räumt dann in jedem Server auf, in dem der Bot zum Start dabei war.
+pub fn spawn_cleanup_task(ctx: poise::serenity_prelude::Context, ready: &Ready, config: Config) {
+	let Some(o_phase_config) = config.o_phase else {
+		return;
+	};
+	let Some(end_time) = o_phase_config.end_time else {
+		return;
+	};
+	let guild_ids: Vec<GuildId> = ready.guilds.iter().map(|guild| guild.id).collect();
+
+	tokio::spawn(async move {
+		// Liegt das Enddatum schon deutlich in der Vergangenheit, ist das meistens
+		// ein Neustart nach Ablauf der O-Phase und kein frischer Start kurz davor.
+		// Ohne diese Schranke würde jeder Neustart die Abschiedsnachricht erneut posten.
+		if Utc::now() - end_time > chrono::Duration::minutes(5) {
+			info!(
+				"O-Phase-Enddatum {:?} liegt bereits mehr als 5 Minuten zurück, überspringe automatische Rollenentfernung (vermutlich Neustart nach Ablauf)",
+				end_time
+			);
+			return;
+		}
+
+		let sleep_duration = (end_time - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+		info!("O-Phase endet in {:?}, Rollen werden danach automatisch entfernt", sleep_duration);
+		tokio::time::sleep(sleep_duration).await;
+
+		for guild_id in guild_ids {
+			if let Err(e) = remove_ophase_roles(&ctx, guild_id, &o_phase_config).await {
+				info!("Konnte O-Phasen-Rollen in Server {:?} nicht entfernen: {e:?}", guild_id);
+			}
+		}
+	});
+}
+
+async fn remove_ophase_roles(ctx: &poise::serenity_prelude::Context, guild_id: GuildId, config: &OPhase) -> Result<(), Error> {
+	let guild = guild_id.to_partial_guild(ctx.http()).await?;
+
+	for group in &config.groups {
+		let Some(role_id) = get_role_id_by_name(&guild, &group.role_name) else {
+			info!("Keine Rolle mit dem Namen '{}' zum Entfernen gefunden", group.role_name);
+			continue;
+		};
+
+		let mut after = None;
+		loop {
+			let members = guild_id.members(ctx.http(), Some(1000), after).await?;
+			let Some(last_member) = members.last() else {
+				break;
+			};
+			after = Some(last_member.user.id);
+
+			for member in members.iter().filter(|member| member.roles.contains(&role_id)) {
+				if let Err(e) = member.remove_role(ctx.http(), role_id).await {
+					info!(
+						"Konnte Rolle '{}' nicht von {} ({}) entfernen: {e:?}",
+						group.role_name, member.user.name, member.user.id
+					);
+				}
+			}
+
+			if members.len() < 1000 {
 				break;
 			}
 		}
-		let invite = invite.expect("Could not find invite for O-Phase code");
 
-		info!("O-Phase invite has {} uses", invite.uses);
+		if let Ok(channel_id) = get_channel_id(ctx.http(), guild_id, &group.channel_name).await {
+			let farewell = CreateMessage::new().content(format!(
+				"Die O-Phase ist jetzt vorbei, die Rolle <@&{}> wurde wieder entfernt. War schön mit euch! :)",
+				role_id
+			));
+			if let Err(e) = channel_id.send_message(ctx.http(), farewell).await {
+				info!("Konnte Abschiedsnachricht in Kanal '{}' nicht senden: {e:?}", group.channel_name);
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Baut die Invite-Nutzungszahlen aller Server beim Start des Bots auf, damit
+/// [`handle_new_guild_member`] später erkennen kann, welcher Code sich geändert hat.
+pub async fn initialize_invite_cache(ctx: &poise::serenity_prelude::Context, ready: &Ready, config: &Config, invite_cache: &InviteUseCache) {
+	if config.o_phase.is_none() {
+		return;
+	}
+
+	// Erst alle Server abfragen und sammeln, ohne den Lock zu halten: sonst
+	// blockiert ein zeitgleicher Beitritt während des Starts auf diesem Mutex,
+	// bis alle HTTP-Anfragen hier durch sind.
+	let mut fetched = Vec::with_capacity(ready.guilds.len());
+	for guild in ready.guilds.iter() {
+		match guild.id.invites(ctx.http()).await {
+			Ok(invites) => {
+				let uses: HashMap<String, u64> = invites.into_iter().map(|invite| (invite.code, invite.uses)).collect();
+				trace!("Invite-Nutzungszahlen für Server {:?} zwischengespeichert: {:?}", guild.id, uses);
+				fetched.push((guild.id, uses));
+			},
+			Err(e) => {
+				info!("Konnte Invites für Server {:?} nicht abrufen: {e:?}", guild.id);
+			},
+		}
+	}
 
-		Some(invite.uses)
-	} else {
-		None
+	let mut cache = invite_cache.lock().await;
+	for (guild_id, uses) in fetched {
+		cache.insert(guild_id, uses);
 	}
 }
 
 pub async fn handle_new_guild_member(
 	ctx: &poise::serenity_prelude::Context,
-	new_member: &poise::serenity_prelude::Member,
+	new_member: &Member,
 	o_phase_config: &OPhase,
-	ophase_invite_uses: &Arc<Mutex<Option<u64>>>,
+	invite_cache: &InviteUseCache,
 ) -> Result<(), Error> {
 	trace!(
-		"Checking invite for new member: {} ({})",
+		"Prüfe Invite für neues Mitglied: {} ({})",
 		new_member.user.name,
 		new_member.user.id
 	);
 
 	let guild = new_member.guild_id;
-	let guild_invites = guild.invites(&ctx.http()).await?;
-	let Some(invite) = guild_invites
-		.into_iter()
-		.find(|invite| invite.code == o_phase_config.invite_code)
-	else {
+
+	// Abruf und Diff müssen unter diesem Lock passieren, nicht davor: sonst
+	// können zwei zeitgleiche Beitritte über unterschiedliche Invites denselben
+	// schon doppelt erhöhten Snapshot sehen und beide denselben Code zugeordnet
+	// bekommen, bevor einer von ihnen den Cache aktualisiert hat.
+	let used_code = {
+		let mut cache = invite_cache.lock().await;
+		let current_invites = guild.invites(&ctx.http()).await?;
+		let cached_uses = cache.entry(guild).or_default();
+
+		let grown: Vec<&str> = current_invites
+			.iter()
+			.filter(|invite| invite.uses > cached_uses.get(&invite.code).copied().unwrap_or(0))
+			.map(|invite| invite.code.as_str())
+			.collect();
+
+		if grown.len() > 1 {
+			warn!(
+				"Mehrdeutige Invite-Zuordnung für {} ({}): mehrere Codes sind zeitgleich gewachsen ({}), nehme den ersten",
+				new_member.user.name,
+				new_member.user.id,
+				grown.join(", ")
+			);
+		}
+
+		let used_code = grown.first().map(|code| code.to_string());
+
+		// Immer neu befüllen, auch ohne Treffer: so bleibt der Cache nicht
+		// veraltet, wenn z.B. ein Einmal-Invite nach Benutzung gelöscht wurde.
+		*cached_uses = current_invites.iter().map(|invite| (invite.code.clone(), invite.uses)).collect();
+
+		used_code
+	};
+
+	let Some(used_code) = used_code else {
+		debug!(
+			"Konnte keinen benutzten Invite-Code für {} ({}) ermitteln (Vanity-URL oder Invite bereits gelöscht?)",
+			new_member.user.name, new_member.user.id
+		);
+		return Ok(());
+	};
+
+	let Some(role_name) = o_phase_config.invite_roles.get(&used_code) else {
+		trace!("Invite-Code '{}' ist keiner O-Phasen-Rolle zugeordnet", used_code);
 		return Ok(());
 	};
-	let new_invite_uses = invite.uses;
-	let ophase_invite_uses = {
-		let mut count = ophase_invite_uses.lock().unwrap();
-		let uses = count.as_mut().unwrap();
-		let previous = *uses;
-		*uses = new_invite_uses;
-		previous
+
+	let partial_guild = guild.to_partial_guild(ctx.http()).await?;
+	let Some(role_id) = get_role_id_by_name(&partial_guild, role_name) else {
+		info!("Keine Rolle mit dem Namen '{}' für Invite-Code '{}' gefunden", role_name, used_code);
+		return Ok(());
 	};
 
-	trace!("Invite uses: new = {}, old = {:?}", new_invite_uses, ophase_invite_uses);
+	info!(
+		"Neues O-Phasen-Mitglied über Invite '{}': {} ({})",
+		used_code, new_member.user.name, new_member.user.id
+	);
+	new_member.add_role(ctx.http(), role_id).await?;
 
-	if new_invite_uses > ophase_invite_uses {
-		info!(
-			"New O-Phase member through invite: {} ({})",
-			new_member.user.name, new_member.user.id
-		);
-		let role_id = get_role_id(guild.to_partial_guild(ctx.http()).await?, o_phase_config).await?;
-		new_member.add_role(ctx.http(), role_id).await?;
-	}
 	Ok(())
 }
 
+/// Schreibt ein Audit-Embed in den konfigurierten `log_channel_name`, falls
+/// vorhanden, damit Organisator:innen Verifizierungsversuche live mitverfolgen
+/// können (z.B. um das Erraten eines Gruppen-Passworts zu bemerken).
+async fn log_verification_attempt(http: impl CacheHttp, config: &OPhase, member: &Member, password: &str, granted_role: Option<&str>) {
+	let Some(log_channel_name) = &config.log_channel_name else {
+		return;
+	};
+	let channel_id = match get_channel_id(&http, member.guild_id, log_channel_name).await {
+		Ok(channel_id) => channel_id,
+		Err(e) => {
+			info!("Log-Kanal '{}' für O-Phase nicht gefunden: {e:?}", log_channel_name);
+			return;
+		},
+	};
+
+	let embed = match granted_role {
+		Some(role_name) => CreateEmbed::new()
+			.color(Color::from_rgb(67, 181, 129))
+			.title("O-Phase Verifizierung erfolgreich")
+			.description(format!("{} (<@{}>) wurde der Gruppe '{}' zugeordnet.", member.user.name, member.user.id, role_name)),
+		None => CreateEmbed::new()
+			.color(Color::from_rgb(255, 99, 71))
+			.title("O-Phase Verifizierung fehlgeschlagen")
+			.description(format!(
+				"{} (<@{}>) hat das Passwort '{}' eingegeben, das zu keiner Gruppe passt.",
+				member.user.name, member.user.id, password
+			)),
+	};
+
+	if let Err(e) = channel_id.send_message(&http, CreateMessage::new().embed(embed)).await {
+		info!("Konnte Audit-Log-Nachricht für O-Phase nicht senden: {e:?}");
+	}
+}
+
+/// Prüft das eingegebene Passwort gegen alle konfigurierten Tutorgruppen,
+/// vergibt bei einem Treffer die Rolle der passenden Gruppe und liefert in
+/// beiden Fällen das Embed für die Antwort.
+async fn verify_password_and_grant_role(http: impl CacheHttp, member: &Member, config: &OPhase, password: &str) -> Result<CreateEmbed, Error> {
+	let Some(group) = find_matching_group(config, password) else {
+		info!("Falsches Passwort '{}': {} ({})", password, member.user.name, member.user.id);
+		log_verification_attempt(&http, config, member, password, None).await;
+
+		return Ok(CreateEmbed::new()
+			.color(Color::from_rgb(255, 99, 71))
+			.title("Falsches Gruppen-Passwort")
+			.description("Sorry, das ist nicht das korrekte Gruppen-Passwort. Frage bitte noch einmal nach :)"));
+	};
+
+	debug!("Richtiges Passwort für Gruppe '{}': {} ({})", group.role_name, member.user.name, member.user.id);
+
+	let role_id =
+		get_role_id_by_name(&member.guild_id.to_partial_guild(&http).await?, &group.role_name).ok_or("Keine Rolle mit dem Namen der O-Phasen-Rolle gefunden")?;
+	member.add_role(&http, role_id).await?;
+	let channel_id = get_channel_id(&http, member.guild_id, &group.channel_name).await?;
+
+	info!("Nutzer zur Gruppe '{}' hinzugefügt: {} ({})", group.role_name, member.user.name, member.user.id);
+	log_verification_attempt(&http, config, member, password, Some(&group.role_name)).await;
+
+	Ok(CreateEmbed::new()
+		.color(Color::from_rgb(25, 177, 241))
+		.title("Willkommen in der kitmatheinfo.de O-Phase!")
+		.description(format!("Wir sehen uns in <#{}> :)", channel_id)))
+}
+
 /// Für Erstis der kitmatheinfo.de O-Phasengruppe
 #[poise::command(slash_command, rename = "ophase")]
 async fn ersti(ctx: poise::ApplicationContext<'_, AppState, Error>) -> Result<(), Error> {
@@ -139,52 +338,191 @@ async fn ersti(ctx: poise::ApplicationContext<'_, AppState, Error>) -> Result<()
 		return Err("O-Phase Funktionalität ist nicht konfiguriert".into());
 	};
 
-	let guild = ctx
-		.guild()
-		.ok_or("Dieser Befehl kann nur in einem Server ausgeführt werden.")?
-		.clone();
-
-	let role_id = get_role_id(guild, config).await?;
-	let channel_id = get_channel_id(ctx, config).await?;
-
 	let Some(response) = PasswordResponse::execute(ctx).await? else {
 		debug!("Abgebrochen: {} ({})", ctx.author().name, ctx.author().id);
 		return Ok(());
 	};
 
-	if response.password.to_lowercase() != config.password.to_lowercase() {
-		info!(
-			"Falsches Passwort '{}': {} ({})",
-			response.password,
-			ctx.author().name,
-			ctx.author().id
-		);
+	let embed = verify_password_and_grant_role(ctx.http(), &member, config, &response.password).await?;
+	ctx.send(CreateReply::default().reply(true).ephemeral(true).embed(embed)).await?;
 
-		let reply = CreateReply::default().ephemeral(true).embed(
-			CreateEmbed::new()
-				.color(Color::from_rgb(255, 99, 71))
-				.title("Falsches Gruppen-Passwort")
-				.description("Sorry, das ist nicht das korrekte Gruppen-Passwort. Frage bitte noch einmal nach :)"),
+	Ok(())
+}
+
+/// Postet einen dauerhaften "Ich bin Ersti"-Button in den aktuellen Kanal. Ein
+/// Klick darauf öffnet dasselbe Passwort-Modal wie `/ophase`, siehe
+/// [`handle_component_interaction`]. Weil der Button über seine stabile
+/// `custom_id` statt über gehaltenen Zustand erkannt wird, funktioniert er auch
+/// nach einem Neustart des Bots noch.
+#[poise::command(slash_command, rename = "ophase-post-button", required_permissions = "MANAGE_GUILD", guild_only)]
+async fn post_verify_button(ctx: poise::ApplicationContext<'_, AppState, Error>) -> Result<(), Error> {
+	if ctx.data.config.o_phase.is_none() {
+		return Err("O-Phase Funktionalität ist nicht konfiguriert".into());
+	}
+
+	let embed = CreateEmbed::new()
+		.color(Color::from_rgb(25, 177, 241))
+		.title("kitmatheinfo.de O-Phase")
+		.description("Klicke auf den Button, um dich mit deinem Gruppen-Passwort zu verifizieren.");
+	let button = CreateButton::new(VERIFY_BUTTON_ID).label("Ich bin Ersti");
+	let message = CreateMessage::new().embed(embed).components(vec![CreateActionRow::Buttons(vec![button])]);
+
+	ctx.channel_id().send_message(ctx.http(), message).await?;
+	ctx.send(CreateReply::default().ephemeral(true).content("Button gepostet.")).await?;
+
+	Ok(())
+}
+
+/// Erlaubt Moderator:innen, die O-Phasen-Konfiguration zur Laufzeit einzusehen
+/// und anzupassen, ohne die TOML-Datei zu editieren und den Bot neu zu starten.
+#[poise::command(
+	slash_command,
+	rename = "ophase-config",
+	required_permissions = "MANAGE_GUILD",
+	guild_only,
+	subcommands("ophase_config_view", "ophase_config_set_group", "ophase_config_set_invite")
+)]
+async fn ophase_config(_ctx: poise::ApplicationContext<'_, AppState, Error>) -> Result<(), Error> {
+	Ok(())
+}
+
+/// Zeigt die aktuell konfigurierten Tutorgruppen und Invite-Zuordnungen an.
+#[poise::command(slash_command, rename = "view", required_permissions = "MANAGE_GUILD")]
+async fn ophase_config_view(ctx: poise::ApplicationContext<'_, AppState, Error>) -> Result<(), Error> {
+	let Some(config) = &ctx.data.config.o_phase else {
+		return Err("O-Phase Funktionalität ist nicht konfiguriert".into());
+	};
+
+	let mut embed = CreateEmbed::new().color(Color::from_rgb(25, 177, 241)).title("O-Phase Konfiguration");
+	for group in &config.groups {
+		embed = embed.field(
+			&group.role_name,
+			format!("Passwort: `{}`\nKanal: #{}", group.password, group.channel_name),
+			false,
 		);
-		ctx.send(reply).await?;
-		return Ok(());
 	}
+	for (code, role_name) in &config.invite_roles {
+		embed = embed.field(format!("Invite '{code}'"), format!("Rolle: {role_name}"), true);
+	}
+
+	ctx.send(CreateReply::default().ephemeral(true).embed(embed)).await?;
+	Ok(())
+}
 
-	debug!("Richtiges Passwort: {} ({})", ctx.author().name, ctx.author().id);
+/// Passt Passwort, Rolle und/oder Kanal einer bestehenden Tutorgruppe an.
+#[poise::command(slash_command, rename = "set-group", required_permissions = "MANAGE_GUILD")]
+async fn ophase_config_set_group(
+	ctx: poise::ApplicationContext<'_, AppState, Error>,
+	#[description = "Aktueller Rollenname der Gruppe"] group: String,
+	#[description = "Neues Passwort"] password: Option<String>,
+	#[description = "Neuer Rollenname"] role_name: Option<String>,
+	#[description = "Neuer Kanalname"] channel_name: Option<String>,
+) -> Result<(), Error> {
+	let found = ctx
+		.data
+		.update_o_phase_config(|o_phase| {
+			let Some(group_config) = o_phase.groups.iter_mut().find(|g| g.role_name == group) else {
+				return false;
+			};
+			if let Some(password) = password {
+				group_config.password = password;
+			}
+			if let Some(role_name) = role_name {
+				group_config.role_name = role_name;
+			}
+			if let Some(channel_name) = channel_name {
+				group_config.channel_name = channel_name;
+			}
+			true
+		})
+		.await?;
 
-	member.add_role(ctx.http(), role_id).await?;
+	if !found {
+		return Err(format!("Keine O-Phasen-Gruppe mit dem Rollennamen '{group}' gefunden").into());
+	}
 
-	info!("Nutzer hinzugefügt: {} ({})", ctx.author().name, ctx.author().id);
+	info!("O-Phasen-Gruppe '{}' wurde von {} ({}) angepasst", group, ctx.author().name, ctx.author().id);
 
-	let reply = ctx.reply_builder(
-		CreateReply::default().reply(true).ephemeral(true).embed(
+	ctx.send(
+		CreateReply::default().ephemeral(true).embed(
 			CreateEmbed::new()
 				.color(Color::from_rgb(25, 177, 241))
-				.title("Willkommen in der kitmatheinfo.de O-Phase!")
-				.description(format!("Wir sehen uns in <#{}> :)", channel_id)),
+				.title("O-Phase Konfiguration aktualisiert")
+				.description(format!("Die Gruppe '{group}' wurde aktualisiert und gespeichert.")),
 		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/// Ordnet einen Invite-Code einer Rolle zu oder entfernt die Zuordnung.
+#[poise::command(slash_command, rename = "set-invite", required_permissions = "MANAGE_GUILD")]
+async fn ophase_config_set_invite(
+	ctx: poise::ApplicationContext<'_, AppState, Error>,
+	#[description = "Invite-Code"] invite_code: String,
+	#[description = "Zuzuordnende Rolle (leer lassen zum Entfernen der Zuordnung)"] role_name: Option<String>,
+) -> Result<(), Error> {
+	ctx.data
+		.update_o_phase_config(|o_phase| {
+			match &role_name {
+				Some(role_name) => {
+					o_phase.invite_roles.insert(invite_code.clone(), role_name.clone());
+				},
+				None => {
+					o_phase.invite_roles.remove(&invite_code);
+				},
+			}
+			true
+		})
+		.await?;
+
+	info!(
+		"Invite-Zuordnung für '{}' wurde von {} ({}) angepasst",
+		invite_code,
+		ctx.author().name,
+		ctx.author().id
 	);
-	ctx.send(reply).await?;
+
+	ctx.send(
+		CreateReply::default().ephemeral(true).embed(
+			CreateEmbed::new()
+				.color(Color::from_rgb(25, 177, 241))
+				.title("O-Phase Konfiguration aktualisiert")
+				.description(format!("Die Invite-Zuordnung für '{invite_code}' wurde gespeichert.")),
+		),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/// Behandelt Klicks auf den von [`post_verify_button`] geposteten Button.
+pub async fn handle_component_interaction(ctx: &poise::serenity_prelude::Context, interaction: &ComponentInteraction, config: &Config) -> Result<(), Error> {
+	if interaction.data.custom_id != VERIFY_BUTTON_ID {
+		return Ok(());
+	}
+
+	let Some(o_phase_config) = &config.o_phase else {
+		return Err("O-Phase Funktionalität ist nicht konfiguriert".into());
+	};
+	let Some(member) = &interaction.member else {
+		return Err("Dieser Button kann nicht in DMs verwendet werden".into());
+	};
+
+	let Some(response) = PasswordResponse::execute_modal_on_component_interaction(ctx.clone(), interaction.clone(), None, None).await? else {
+		debug!("Abgebrochen: {} ({})", member.user.name, member.user.id);
+		return Ok(());
+	};
+
+	let embed = verify_password_and_grant_role(ctx, member, o_phase_config, &response.password).await?;
+
+	interaction
+		.create_response(
+			ctx.http(),
+			CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().ephemeral(true).embed(embed)),
+		)
+		.await?;
 
 	Ok(())
 }